@@ -24,6 +24,68 @@ where
         );
         Ok(transactionss)
     }
+
+    fn build_best_block(
+        &self,
+        block_id: Option<BlockId>,
+        account_overrides: Option<HashMap<Address, AccountOverride>>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> RpcResult<SealedBlock> {
+        let ctx = StateContext {
+            block_id,
+            account_overrides: account_overrides.unwrap_or_default(),
+            block_overrides,
+        };
+        let ordered = self.best_transactions_in_priority_order();
+
+        let block = self.runner.build_best_block(ordered, &ctx)?;
+
+        info!(
+            "Built block {} with {} transactions",
+            block.number,
+            block.body.len()
+        );
+        Ok(block)
+    }
+
+    fn trace_best_transactions(
+        &self,
+        block_id: Option<BlockId>,
+        account_overrides: Option<HashMap<Address, AccountOverride>>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> RpcResult<Vec<TracedTx>> {
+        let ctx = StateContext {
+            block_id,
+            account_overrides: account_overrides.unwrap_or_default(),
+            block_overrides,
+        };
+        let ordered = self.best_transactions_in_priority_order();
+
+        let traces = self.runner.simulate_with_trace(ordered, &ctx)?;
+
+        info!("Traced {} best-pool transactions", traces.len());
+        Ok(traces)
+    }
+}
+
+impl<Pool> TxpoolExt<Pool>
+where
+    Pool: TransactionPool + Clone + 'static,
+{
+    /// Flattens `best_transactions()` into a `Vec` in the pool's own priority order.
+    /// Both `RethRunner::build_best_block` and `RethRunner::simulate_with_trace` rely
+    /// on that order (for the gas-limit cutoff and for nonce/balance carry-over
+    /// respectively), so it must be preserved rather than discarded by grouping into a
+    /// `HashMap`, whose iteration order is unrelated to priority.
+    fn best_transactions_in_priority_order(&self) -> Vec<(Address, TransactionSigned)> {
+        self.pool
+            .best_transactions()
+            .map(|tx| {
+                let recovered = tx.to_recovered_transaction();
+                (recovered.signer(), recovered.into_signed())
+            })
+            .collect()
+    }
 }
 
 fn main() {
@@ -39,7 +101,23 @@ fn main() {
                     // here we get the configured pool.
                     let pool = ctx.pool().clone();
 
-                    let ext = TxpoolExt { pool };
+                    // standalone runner over its own read-only view of the db, used to
+                    // simulate and assemble blocks out of the pool's best transactions.
+                    // Targets the same chain as the running node rather than assuming
+                    // mainnet.
+                    let mut runner_builder = RethRunnerBuilder::new();
+                    runner_builder
+                        .with_db_path(
+                            std::env::var("RETH_DB_PATH").unwrap_or_else(|_| "./".to_string()),
+                        )
+                        .with_chain(ctx.config().chain.clone());
+                    let runner = Arc::new(
+                        runner_builder
+                            .build()
+                            .expect("failed to build reth runner"),
+                    );
+
+                    let ext = TxpoolExt { pool, runner };
 
                     // now we merge our extension namespace into all configured transports
                     ctx.modules.merge_configured(ext.into_rpc()).unwrap();
@@ -73,16 +151,52 @@ pub trait TxpoolExtApi {
     /// Returns the number of transactions in the pool.
     #[method(name = "getBestTransactions")]
     fn best_transactions(&self) -> RpcResult<Vec<TransactionSigned>>;
+
+    /// Assembles a sealed block out of the best pool transactions and returns it.
+    ///
+    /// `block_id` pins simulation to a historical block instead of the latest one;
+    /// `account_overrides`/`block_overrides` patch account or block-env fields before
+    /// executing, mirroring `eth_callMany`'s override objects.
+    #[method(name = "buildBestBlock")]
+    fn build_best_block(
+        &self,
+        block_id: Option<BlockId>,
+        account_overrides: Option<HashMap<Address, AccountOverride>>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> RpcResult<SealedBlock>;
+
+    /// Simulates the best pool transactions with a tracing inspector attached and
+    /// returns each one's call frames, logs, and access list.
+    ///
+    /// Accepts the same `block_id`/`account_overrides`/`block_overrides` params as
+    /// [`TxpoolExtApi::build_best_block`].
+    #[method(name = "traceBestTransactions")]
+    fn trace_best_transactions(
+        &self,
+        block_id: Option<BlockId>,
+        account_overrides: Option<HashMap<Address, AccountOverride>>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> RpcResult<Vec<TracedTx>>;
 }
 /// The type that implements the `txpool` rpc namespace trait
 pub struct TxpoolExt<Pool> {
     pool: Pool,
+    runner: Arc<RethRunner<Arc<reth_db::mdbx::DatabaseEnv>>>,
 }
 
+mod simulation;
+
 use clap::Parser;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth::cli::Cli;
-use reth::primitives::{IntoRecoveredTransaction, TransactionSigned};
+use reth::primitives::{
+    Address, BlockId, IntoRecoveredTransaction, SealedBlock, TransactionSigned,
+};
 use reth_node_ethereum::EthereumNode;
 use reth_transaction_pool::TransactionPool;
+use simulation::{
+    AccountOverride, BlockOverrides, RethRunner, RethRunnerBuilder, StateContext, TracedTx,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
 use tracing::info;
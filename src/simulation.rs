@@ -17,19 +17,24 @@ use reth_db::{
 };
 use reth_evm_ethereum::{execute::EthExecutorProvider, EthEvmConfig};
 use reth_primitives::{
+    proofs::{calculate_receipt_root, calculate_transaction_root, calculate_withdrawals_root},
     revm::env::{fill_block_env, fill_tx_env, tx_env_with_recovered},
     revm_primitives::EVMError,
-    BlockNumberOrTag, ChainSpec, ChainSpecBuilder, Header, B256,
+    AccessList, Block, BlockId, BlockNumberOrTag, Bytes, ChainSpec, ChainSpecBuilder, Header,
+    Receipt, SealedBlock, SealedHeader, B256,
 };
 use reth_provider::{
     providers::{BlockchainProvider, StaticFileProvider},
     AccountReader, BlockNumReader, BlockReader, BlockReaderIdExt, BlockSource, HeaderProvider,
-    ReceiptProvider, StateProvider, StateProviderFactory, TransactionsProvider,
+    ProviderError, ReceiptProvider, StateProvider, StateProviderFactory, TransactionsProvider,
 };
 use reth_revm::{
     database::StateProviderDatabase,
     db::CacheDB,
-    primitives::{EnvWithHandlerCfg, ResultAndState, TransactTo, TxEnv},
+    primitives::{
+        Bytecode, EnvWithHandlerCfg, ExecutionResult, ResultAndState, TransactTo, TxEnv, U256,
+    },
+    tracing::{types::CallTraceArena, TracingInspector, TracingInspectorConfig},
     DBBox, Evm, StateBuilder, StateDBBox,
 };
 
@@ -42,63 +47,526 @@ pub struct RethRunner<DB> {
     pub provider: Arc<BlockchainProvider<DB>>,
 }
 
-pub fn simulate(txs: HashMap<Option<Address>, Vec<TransactionSigned>>) -> eyre::Result<()> {
-    Ok(())
+/// The `CacheDB` used while simulating a batch: a boxed state provider for the target
+/// block, wrapped in an `Arc` so the same snapshot can be reused across txs without
+/// cloning the underlying provider.
+type SimDb = CacheDB<Arc<StateProviderDatabase<Box<dyn StateProvider>>>>;
+
+/// The outcome of a single transaction that was executed as part of a
+/// sequential, same-sender-aware batch simulation.
+#[derive(Debug, Clone)]
+pub struct SimulatedTx {
+    pub tx: TransactionSigned,
+    pub result: ExecutionResult,
+    pub gas_used: u64,
+    pub cumulative_gas_used: u64,
+}
+
+/// The trace produced for a single transaction executed with a revm `Inspector`
+/// attached, instead of just the raw `ResultAndState` that [`RethRunner::simulate`]
+/// yields: call frames, per-call gas, logs, and the EIP-2930 access list the tx would
+/// need if it were resubmitted with one attached.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TracedTx {
+    pub tx: TransactionSigned,
+    pub result: ExecutionResult,
+    pub trace: CallTraceArena,
+    pub access_list: AccessList,
+}
+
+/// Per-account state override, mirroring the `eth_callMany`/`eth_call` override object:
+/// lets a caller patch balance, nonce, code, and individual storage slots before a
+/// simulation runs, without those changes ever touching the real state provider.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct AccountOverride {
+    pub balance: Option<U256>,
+    pub nonce: Option<u64>,
+    pub code: Option<Bytes>,
+    pub state_diff: Option<HashMap<U256, U256>>,
+}
+
+/// Overrides applied to the block environment before executing, e.g. to simulate
+/// against a different timestamp/basefee/coinbase than the target block's own.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct BlockOverrides {
+    pub number: Option<U256>,
+    pub timestamp: Option<U256>,
+    pub base_fee: Option<U256>,
+    pub coinbase: Option<Address>,
+    pub gas_limit: Option<U256>,
+}
+
+/// Execution context for a `run`/`simulate` call: which block to execute against
+/// (defaulting to the latest), plus any account or block field overrides to apply
+/// first. Modeled on the `StateContext` + override objects used by `eth_callMany`.
+#[derive(Debug, Clone, Default)]
+pub struct StateContext {
+    pub block_id: Option<BlockId>,
+    pub account_overrides: HashMap<Address, AccountOverride>,
+    pub block_overrides: Option<BlockOverrides>,
+}
+
+/// Errors raised while resolving state or executing transactions through a
+/// [`RethRunner`]. Replaces the earlier practice of collapsing every failure into
+/// `EVMError::Database(String)`, which made "missing header" and "tx reverted"
+/// indistinguishable to callers.
+#[derive(Debug, thiserror::Error)]
+pub enum RethRunnerError {
+    #[error("provider error: {0}")]
+    Provider(#[from] ProviderError),
+    #[error("block not found for the requested block id")]
+    BlockNotFound,
+    #[error("EVM execution failed: {0}")]
+    Evm(#[from] EVMError<ProviderError>),
+}
+
+// Lets the DB-generic helpers above (`apply_account_overrides`, `has_sufficient_balance`)
+// run against `CacheDB<EmptyDB>` in unit tests, since `EmptyDB`'s `DatabaseRef::Error` is
+// `Infallible` rather than `ProviderError`.
+impl From<std::convert::Infallible> for RethRunnerError {
+    fn from(err: std::convert::Infallible) -> Self {
+        match err {}
+    }
+}
+
+// Same reasoning as the `Infallible` -> `RethRunnerError` impl above, but for the EVM's
+// own error type: lets `execute_if_valid` (generic over `ExtDB`) run against
+// `CacheDB<EmptyDB>` in unit tests. `Infallible` is uninhabited, so mapping the
+// `Database` variant's inner error through it can never actually run.
+impl From<EVMError<std::convert::Infallible>> for RethRunnerError {
+    fn from(err: EVMError<std::convert::Infallible>) -> Self {
+        RethRunnerError::Evm(err.map_db_err(|err| match err {}))
+    }
+}
+
+impl From<RethRunnerError> for jsonrpsee::types::ErrorObjectOwned {
+    fn from(err: RethRunnerError) -> Self {
+        let code = match &err {
+            RethRunnerError::BlockNotFound => jsonrpsee::types::error::INVALID_PARAMS_CODE,
+            RethRunnerError::Provider(_) | RethRunnerError::Evm(_) => {
+                jsonrpsee::types::error::INTERNAL_ERROR_CODE
+            }
+        };
+        jsonrpsee::types::ErrorObjectOwned::owned(code, err.to_string(), None::<()>)
+    }
 }
 
 impl<DB> RethRunner<DB> {
     pub fn new(spec: Arc<ChainSpec>, provider: Arc<BlockchainProvider<DB>>) -> Self {
         Self { spec, provider }
     }
+
+    /// Writes account overrides directly into the `CacheDB` so they're visible to the
+    /// EVM without ever touching the real state provider underneath it.
+    fn apply_account_overrides<ExtDB>(
+        db: &mut CacheDB<ExtDB>,
+        overrides: &HashMap<Address, AccountOverride>,
+    ) -> Result<(), RethRunnerError>
+    where
+        ExtDB: reth_revm::db::DatabaseRef,
+        RethRunnerError: From<ExtDB::Error>,
+    {
+        for (address, over) in overrides {
+            let mut info = db.basic(*address)?.unwrap_or_default();
+
+            if let Some(balance) = over.balance {
+                info.balance = balance;
+            }
+            if let Some(nonce) = over.nonce {
+                info.nonce = nonce;
+            }
+            if let Some(code) = &over.code {
+                info.code = Some(Bytecode::new_raw(code.clone()));
+            }
+            db.insert_account_info(*address, info);
+
+            if let Some(state_diff) = &over.state_diff {
+                for (slot, value) in state_diff {
+                    db.insert_account_storage(*address, *slot, *value)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks whether `tx` can still execute against the account state currently in
+    /// `db`: the nonce must match exactly, and the balance must cover the tx's value
+    /// plus its full gas allowance (`gas_limit * max_fee_per_gas`), not just the value.
+    /// Used to drop txs invalidated by an earlier tx in the same batch instead of
+    /// letting them hit revm's own pre-validation and abort the whole simulation.
+    fn has_sufficient_balance<ExtDB>(
+        db: &mut CacheDB<ExtDB>,
+        sender: Address,
+        tx: &TransactionSigned,
+    ) -> Result<bool, RethRunnerError>
+    where
+        ExtDB: reth_revm::db::DatabaseRef,
+        RethRunnerError: From<ExtDB::Error>,
+    {
+        let info = db.basic(sender)?.unwrap_or_default();
+        let max_cost = U256::from(tx.gas_limit()) * U256::from(tx.max_fee_per_gas())
+            + U256::from(tx.value());
+
+        Ok(info.nonce == tx.nonce() && info.balance >= max_cost)
+    }
+
+    /// Applies block field overrides to an already-filled `BlockEnv`.
+    fn apply_block_overrides(
+        block_env: &mut reth_revm::primitives::BlockEnv,
+        overrides: Option<&BlockOverrides>,
+    ) {
+        let Some(overrides) = overrides else {
+            return;
+        };
+
+        if let Some(number) = overrides.number {
+            block_env.number = number;
+        }
+        if let Some(timestamp) = overrides.timestamp {
+            block_env.timestamp = timestamp;
+        }
+        if let Some(base_fee) = overrides.base_fee {
+            block_env.basefee = base_fee;
+        }
+        if let Some(coinbase) = overrides.coinbase {
+            block_env.coinbase = coinbase;
+        }
+        if let Some(gas_limit) = overrides.gas_limit {
+            block_env.gas_limit = gas_limit;
+        }
+    }
+
+    /// Keeps the longest priority-ordered prefix of `outcomes` whose cumulative gas
+    /// fits under `gas_limit`, converting each kept outcome into its tx and receipt.
+    /// Pulled out of [`Self::build_best_block`] so the cutoff logic can be exercised
+    /// directly against synthetic [`SimulatedTx`] values, without a live provider.
+    fn select_gas_bounded_prefix(
+        outcomes: Vec<SimulatedTx>,
+        gas_limit: u64,
+    ) -> (Vec<TransactionSigned>, Vec<Receipt>, u64) {
+        let mut included_txs = Vec::new();
+        let mut receipts = Vec::new();
+        let mut cumulative_gas_used = 0u64;
+
+        for outcome in outcomes {
+            if cumulative_gas_used + outcome.gas_used > gas_limit {
+                // Block is full; lower-priority txs are left for the next block.
+                break;
+            }
+            cumulative_gas_used += outcome.gas_used;
+
+            receipts.push(Receipt {
+                tx_type: outcome.tx.tx_type(),
+                success: outcome.result.is_success(),
+                cumulative_gas_used,
+                logs: outcome.result.into_logs(),
+            });
+            included_txs.push(outcome.tx);
+        }
+
+        (included_txs, receipts, cumulative_gas_used)
+    }
 }
 
 impl<DB> RethRunner<DB>
 where
     DB: Database,
 {
+    /// Resolves the header to execute against: the target `block_id` if one was given
+    /// (so simulations can be pinned to a historical block for reproducibility), or the
+    /// chain's latest header otherwise.
+    fn resolve_header(&self, block_id: Option<BlockId>) -> Result<SealedHeader, RethRunnerError> {
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+
+        let block = match block_id {
+            BlockId::Hash(hash) => self.provider.block_by_hash(hash.block_hash)?,
+            BlockId::Number(number) => self.provider.block_by_number_or_tag(number)?,
+        };
+
+        let block = block.ok_or(RethRunnerError::BlockNotFound)?;
+        Ok(block.header.seal_slow())
+    }
+
     fn run(
         &self,
         tx: &TransactionSigned,
         sender: Address,
-    ) -> Result<ResultAndState, EVMError<String>> {
-        let latest_block_header = self
-            .provider
-            .latest_header()
-            .map_err(|_e| EVMError::Database(String::from("Error fetching latest sealed header")))?
-            .unwrap();
-
-        let latest_block = self
-            .provider
-            .block_by_hash(latest_block_header.hash())
-            .map_err(|_e| EVMError::Database(String::from("Error fetching latest block")))?
-            .unwrap();
-
-        let latest_state = self
-            .provider
-            .state_by_block_hash(latest_block_header.hash())
-            .map_err(|_| EVMError::Database(String::from("Error fetching latest state")))?;
-
-        let state = Arc::new(StateProviderDatabase::new(latest_state));
-        let db = CacheDB::new(Arc::clone(&state));
-        // let mut evm = Evm::builder().with_db(db).with_cfg_env_with_handler_cfg(cfg_env_and_spec_id)
+        ctx: &StateContext,
+    ) -> Result<ResultAndState, RethRunnerError> {
+        let header = self.resolve_header(ctx.block_id)?;
+        let state = self.provider.state_by_block_hash(header.hash())?;
+
+        let state = Arc::new(StateProviderDatabase::new(state));
+        let mut db = CacheDB::new(Arc::clone(&state));
+        Self::apply_account_overrides(&mut db, &ctx.account_overrides)?;
+
         let evm_config = EthEvmConfig::default();
         let mut evm = evm_config.evm(db);
-        fill_block_env(evm.block_mut(), &self.spec, &latest_block_header, true);
+        fill_block_env(evm.block_mut(), &self.spec, &header, true);
+        Self::apply_block_overrides(evm.block_mut(), ctx.block_overrides.as_ref());
         fill_tx_env(evm.tx_mut(), tx, sender);
 
-        evm.transact()
-            .map_err(|_| EVMError::Database(String::from("Error executing transaction")))
+        Ok(evm.transact()?)
+    }
+
+    /// Shared execution step of [`Self::simulate`]/[`Self::simulate_with_trace`]: runs
+    /// `tx` against `db`/`env` if `sender` still has a matching nonce and can cover the
+    /// full gas allowance, committing state on success. Returns the (possibly advanced)
+    /// `db` alongside the result, or `None` if the tx was dropped rather than executed
+    /// (stale nonce/balance, or revm's own pre-validation rejected it) — callers keep
+    /// going with the rest of the batch either way instead of aborting it.
+    ///
+    /// `inspector` is optional so `simulate`'s plain batch doesn't pay for call-frame
+    /// tracing that only `simulate_with_trace` needs.
+    ///
+    /// Generic over `ExtDB` (rather than hardcoded to the production [`SimDb`]), like
+    /// `apply_account_overrides`/`has_sufficient_balance` above, so the same-sender
+    /// drain-then-drop invariant this batch relies on can be driven directly in unit
+    /// tests with `CacheDB<EmptyDB>`.
+    fn execute_if_valid<ExtDB>(
+        evm_config: &EthEvmConfig,
+        mut db: CacheDB<ExtDB>,
+        env: &EnvWithHandlerCfg,
+        sender: Address,
+        tx: &TransactionSigned,
+        inspector: Option<&mut TracingInspector>,
+    ) -> Result<(CacheDB<ExtDB>, Option<ExecutionResult>), RethRunnerError>
+    where
+        ExtDB: reth_revm::db::DatabaseRef,
+        RethRunnerError: From<ExtDB::Error> + From<EVMError<ExtDB::Error>>,
+    {
+        if !Self::has_sufficient_balance(&mut db, sender, tx)? {
+            // Stale nonce, or balance/gas allowance drained by an earlier tx in this
+            // batch; drop it instead of aborting the rest of the batch.
+            return Ok((db, None));
+        }
+
+        let mut tx_env = env.clone();
+        fill_tx_env(&mut tx_env.tx, tx, sender);
+
+        let transact_result = match inspector {
+            Some(inspector) => {
+                let mut evm = evm_config.evm_with_env_and_inspector(db, tx_env, inspector);
+                let result = evm.transact();
+                db = evm.into_db_and_env_with_handler_cfg().0;
+                result
+            }
+            None => {
+                let mut evm = evm_config.evm_with_env(db, tx_env);
+                let result = evm.transact();
+                db = evm.into_db_and_env_with_handler_cfg().0;
+                result
+            }
+        };
+
+        match transact_result {
+            Ok(ResultAndState { result, state }) => {
+                db.commit(state);
+                Ok((db, Some(result)))
+            }
+            Err(EVMError::Transaction(_)) => {
+                // `has_sufficient_balance` is a simplification (e.g. it doesn't model
+                // EIP-1559 priority-fee-vs-basefee rules); fall back to revm's own
+                // pre-validation and drop just this tx rather than aborting every
+                // other sender's batch.
+                Ok((db, None))
+            }
+            Err(other) => Err(other.into()),
+        }
+    }
+
+    /// Executes a priority-ordered batch of pooled transactions against a single
+    /// `CacheDB`, committing state after every successful transaction.
+    ///
+    /// `best_transactions()` can return several transactions from the same sender where
+    /// the first drains the sender's balance and invalidates the second. Reusing one
+    /// `CacheDB` across the whole batch (instead of a fresh one per tx) makes
+    /// nonce/balance/storage changes from tx 1 visible when tx 2 runs, so the
+    /// simulation matches what would actually happen if the batch were included in a
+    /// block. `txs` must already be in the pool's priority order (e.g. via
+    /// `best_transactions()`); execution order matters both for which gas-bounded
+    /// prefix ends up in a block and for nonce/balance carry-over, so it's taken as a
+    /// flat, ordered `Vec` rather than grouped into a `HashMap`, which has no defined
+    /// iteration order. Transactions whose sender no longer has a matching nonce or
+    /// sufficient balance are dropped rather than aborting the batch.
+    pub fn simulate(
+        &self,
+        txs: Vec<(Address, TransactionSigned)>,
+        ctx: &StateContext,
+    ) -> Result<Vec<SimulatedTx>, RethRunnerError> {
+        let header = self.resolve_header(ctx.block_id)?;
+        let state = self.provider.state_by_block_hash(header.hash())?;
+
+        let state = Arc::new(StateProviderDatabase::new(state));
+        let mut db = CacheDB::new(Arc::clone(&state));
+        Self::apply_account_overrides(&mut db, &ctx.account_overrides)?;
+
+        let evm_config = EthEvmConfig::default();
+        let mut evm = evm_config.evm(db);
+        fill_block_env(evm.block_mut(), &self.spec, &header, true);
+        Self::apply_block_overrides(evm.block_mut(), ctx.block_overrides.as_ref());
+        let (mut db, env) = evm.into_db_and_env_with_handler_cfg();
+
+        let mut outcomes = Vec::new();
+        let mut cumulative_gas_used = 0u64;
+
+        for (sender, tx) in txs {
+            let (next_db, result) =
+                Self::execute_if_valid(&evm_config, db, &env, sender, &tx, None)?;
+            db = next_db;
+
+            let Some(result) = result else {
+                continue;
+            };
+
+            let gas_used = result.gas_used();
+            cumulative_gas_used += gas_used;
+
+            outcomes.push(SimulatedTx {
+                tx,
+                result,
+                gas_used,
+                cumulative_gas_used,
+            });
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Same sequential, state-carrying execution as [`Self::simulate`], but with a
+    /// [`TracingInspector`] attached to each transaction so the caller gets call frames,
+    /// per-call gas, logs, and an access list back instead of just `ResultAndState`.
+    /// Handy for debugging why a pooled tx reverts during block assembly.
+    pub fn simulate_with_trace(
+        &self,
+        txs: Vec<(Address, TransactionSigned)>,
+        ctx: &StateContext,
+    ) -> Result<Vec<TracedTx>, RethRunnerError> {
+        let header = self.resolve_header(ctx.block_id)?;
+        let state = self.provider.state_by_block_hash(header.hash())?;
+
+        let state = Arc::new(StateProviderDatabase::new(state));
+        let mut db = CacheDB::new(Arc::clone(&state));
+        Self::apply_account_overrides(&mut db, &ctx.account_overrides)?;
+
+        let evm_config = EthEvmConfig::default();
+        let mut evm = evm_config.evm(db);
+        fill_block_env(evm.block_mut(), &self.spec, &header, true);
+        Self::apply_block_overrides(evm.block_mut(), ctx.block_overrides.as_ref());
+        let (mut db, env) = evm.into_db_and_env_with_handler_cfg();
+
+        let mut traces = Vec::new();
+
+        for (sender, tx) in txs {
+            let mut inspector = TracingInspector::new(TracingInspectorConfig::default_parity());
+            let (next_db, result) =
+                Self::execute_if_valid(&evm_config, db, &env, sender, &tx, Some(&mut inspector))?;
+            db = next_db;
+
+            let Some(result) = result else {
+                continue;
+            };
+
+            let trace = inspector.traces().clone();
+            traces.push(TracedTx {
+                access_list: inspector.into_access_list(),
+                trace,
+                tx,
+                result,
+            });
+        }
+
+        Ok(traces)
+    }
+
+    /// Assembles a full block out of the best pool transactions.
+    ///
+    /// Runs [`Self::simulate`] over `txs`, keeps successes in priority order up to the
+    /// block's gas limit (the target block's own, or `ctx.block_overrides.gas_limit` if
+    /// one was supplied, matching the limit already applied to the EVM's `block_env` via
+    /// [`Self::apply_block_overrides`]), and seals the result into a [`SealedBlock`].
+    /// This is a standalone local builder for testing and MEV experiments, not a consensus-grade
+    /// payload builder: it doesn't run fork choice, doesn't broadcast the block, reuses
+    /// the parent's `base_fee_per_gas` rather than recomputing it, and **does not compute
+    /// a real `state_root`** (it's left at its zero default). A correct `state_root`
+    /// needs a trie walk over the post-execution state diff against the parent's trie,
+    /// which this crate doesn't wire up; treat the returned block as an execution-only
+    /// preview of inclusion/ordering/gas usage, not as something that could be appended
+    /// to the chain as-is.
+    pub fn build_best_block(
+        &self,
+        txs: Vec<(Address, TransactionSigned)>,
+        ctx: &StateContext,
+    ) -> Result<SealedBlock, RethRunnerError> {
+        let latest_block_header = self.resolve_header(ctx.block_id)?;
+
+        let outcomes = self.simulate(txs, ctx)?;
+        let gas_limit = ctx
+            .block_overrides
+            .as_ref()
+            .and_then(|overrides| overrides.gas_limit)
+            .and_then(|gas_limit| u64::try_from(gas_limit).ok())
+            .unwrap_or(latest_block_header.gas_limit);
+        let (included_txs, receipts, cumulative_gas_used) =
+            Self::select_gas_bounded_prefix(outcomes, gas_limit);
+
+        // Mirrors the timestamp `simulate()` actually ran the EVM against (the parent's
+        // plus 12 seconds, or `ctx.block_overrides.timestamp` if one was supplied via
+        // `apply_block_overrides`), so the fork-activation check below agrees with the
+        // rules that were in effect during execution.
+        let timestamp = ctx
+            .block_overrides
+            .as_ref()
+            .and_then(|overrides| overrides.timestamp)
+            .and_then(|timestamp| u64::try_from(timestamp).ok())
+            .unwrap_or(latest_block_header.timestamp + 12);
+        // `resolve_header` can pin `ctx.block_id` to any historical block, so the
+        // built block's own withdrawals shape must follow the fork schedule at its
+        // timestamp rather than always assuming post-Shanghai.
+        let withdrawals = self
+            .spec
+            .is_shanghai_active_at_timestamp(timestamp)
+            .then(Vec::new);
+
+        let header = Header {
+            parent_hash: latest_block_header.hash(),
+            number: latest_block_header.number + 1,
+            gas_limit,
+            gas_used: cumulative_gas_used,
+            timestamp,
+            base_fee_per_gas: latest_block_header.base_fee_per_gas,
+            transactions_root: calculate_transaction_root(&included_txs),
+            receipts_root: calculate_receipt_root(
+                &receipts.into_iter().map(|r| r.with_bloom()).collect::<Vec<_>>(),
+            ),
+            withdrawals_root: withdrawals.as_ref().map(|w| calculate_withdrawals_root(w)),
+            // Left at the zero default: see the doc comment above.
+            state_root: Default::default(),
+            ..Default::default()
+        };
+
+        let block = Block {
+            header,
+            body: included_txs,
+            ommers: Vec::new(),
+            withdrawals,
+        };
+
+        Ok(block.seal_slow())
     }
 }
 
 pub struct RethRunnerBuilder {
     pub db_path: String,
+    pub chain_spec: Arc<ChainSpec>,
 }
 
 impl RethRunnerBuilder {
     pub fn new() -> Self {
         Self {
             db_path: "./".to_string(),
+            chain_spec: Arc::new(ChainSpecBuilder::mainnet().build()),
         }
     }
 
@@ -107,14 +575,37 @@ impl RethRunnerBuilder {
         self
     }
 
+    /// Targets a specific chain instead of the hardcoded mainnet spec, matching this
+    /// crate's own `--chain holesky` example. Takes a plain `Arc<ChainSpec>` so it also
+    /// covers custom/OP-stack/BSC-style specs, not just the well-known ones.
+    pub fn with_chain(&mut self, chain_spec: Arc<ChainSpec>) -> &mut Self {
+        self.chain_spec = chain_spec;
+        self
+    }
+
+    /// Convenience for selecting one of the well-known chains by name, as accepted by
+    /// this crate's own `--chain` CLI flag, instead of constructing the `ChainSpec` by
+    /// hand.
+    pub fn with_chain_named(&mut self, name: &str) -> eyre::Result<&mut Self> {
+        let chain_spec = match name {
+            "mainnet" => reth_primitives::MAINNET.clone(),
+            "sepolia" => reth_primitives::SEPOLIA.clone(),
+            "holesky" => reth_primitives::HOLESKY.clone(),
+            other => eyre::bail!("unsupported chain: {other}"),
+        };
+        Ok(self.with_chain(chain_spec))
+    }
+
     pub fn build(&self) -> eyre::Result<RethRunner<Arc<reth_db::mdbx::DatabaseEnv>>> {
-        let path = std::env::var("RETH_DB_PATH")?;
-        let db_path = Path::new(&path);
+        // The static-files path and db path are both derived from `db_path` so one
+        // node's datadir drives the whole runner, rather than mixing `self.db_path`
+        // with the `RETH_DB_PATH` env var.
+        let db_path = Path::new(&self.db_path);
         let db = Arc::new(open_db_read_only(
             db_path.join("db").as_path(),
             DatabaseArguments::new(ClientVersion::default()),
         )?);
-        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().build());
+        let chain_spec = self.chain_spec.clone();
         let factory =
             ProviderFactory::new(db.clone(), chain_spec.clone(), db_path.join("static_files"))?;
 
@@ -132,3 +623,226 @@ impl RethRunnerBuilder {
         Ok(RethRunner::new(chain_spec, provider))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Signature, Transaction, TxKind, TxLegacy};
+    use reth_revm::db::EmptyDB;
+    use reth_revm::primitives::AccountInfo;
+
+    fn legacy_tx(nonce: u64, gas_limit: u64, gas_price: u128, value: u128) -> TransactionSigned {
+        let tx = Transaction::Legacy(TxLegacy {
+            chain_id: None,
+            nonce,
+            gas_price,
+            gas_limit,
+            to: TxKind::Call(Address::ZERO),
+            value: U256::from(value),
+            input: Bytes::new(),
+        });
+        TransactionSigned::from_transaction_and_signature(
+            tx,
+            Signature {
+                r: U256::from(1),
+                s: U256::from(1),
+                odd_y_parity: false,
+            },
+        )
+    }
+
+    fn db_with_account(address: Address, balance: U256, nonce: u64) -> CacheDB<EmptyDB> {
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            address,
+            AccountInfo {
+                balance,
+                nonce,
+                ..Default::default()
+            },
+        );
+        db
+    }
+
+    /// A `block_env` with enough headroom (gas limit, zero basefee) that a plain legacy
+    /// value transfer executes rather than getting rejected by revm's own pre-validation.
+    fn test_env() -> EnvWithHandlerCfg {
+        let spec = Arc::new(ChainSpecBuilder::mainnet().build());
+        let header = Header {
+            gas_limit: 30_000_000,
+            ..Default::default()
+        }
+        .seal_slow();
+
+        let evm_config = EthEvmConfig::default();
+        let mut evm = evm_config.evm(CacheDB::new(EmptyDB::default()));
+        fill_block_env(evm.block_mut(), &spec, &header, true);
+        evm.into_db_and_env_with_handler_cfg().1
+    }
+
+    #[test]
+    fn drops_tx_with_stale_nonce() {
+        let sender = Address::random();
+        let mut db = db_with_account(sender, U256::MAX, 1);
+        let tx = legacy_tx(0, 21_000, 1, 0);
+
+        assert!(!RethRunner::<EmptyDB>::has_sufficient_balance(&mut db, sender, &tx).unwrap());
+    }
+
+    #[test]
+    fn drops_tx_that_cannot_cover_gas_cost() {
+        let sender = Address::random();
+        // Enough to cover `value` alone, but nowhere near `gas_limit * gas_price`.
+        let mut db = db_with_account(sender, U256::from(1), 0);
+        let tx = legacy_tx(0, 21_000, 1_000_000_000, 1);
+
+        assert!(!RethRunner::<EmptyDB>::has_sufficient_balance(&mut db, sender, &tx).unwrap());
+    }
+
+    #[test]
+    fn accepts_tx_with_matching_nonce_and_sufficient_balance() {
+        let sender = Address::random();
+        let mut db = db_with_account(sender, U256::MAX, 0);
+        let tx = legacy_tx(0, 21_000, 1_000_000_000, 1);
+
+        assert!(RethRunner::<EmptyDB>::has_sufficient_balance(&mut db, sender, &tx).unwrap());
+    }
+
+    fn simulated(tx: TransactionSigned, gas_used: u64, cumulative_gas_used: u64) -> SimulatedTx {
+        SimulatedTx {
+            tx,
+            result: ExecutionResult::Success {
+                reason: reth_revm::primitives::SuccessReason::Stop,
+                gas_used,
+                gas_refunded: 0,
+                logs: Vec::new(),
+                output: reth_revm::primitives::Output::Call(Bytes::new()),
+            },
+            gas_used,
+            cumulative_gas_used,
+        }
+    }
+
+    #[test]
+    fn account_overrides_patch_balance_nonce_and_code() {
+        let address = Address::random();
+        let mut db = CacheDB::new(EmptyDB::default());
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            address,
+            AccountOverride {
+                balance: Some(U256::from(42)),
+                nonce: Some(7),
+                code: Some(Bytes::from_static(&[0x60, 0x00])),
+                state_diff: None,
+            },
+        );
+
+        RethRunner::<EmptyDB>::apply_account_overrides(&mut db, &overrides).unwrap();
+
+        let info = db.basic(address).unwrap().unwrap();
+        assert_eq!(info.balance, U256::from(42));
+        assert_eq!(info.nonce, 7);
+        assert!(info.code.is_some());
+    }
+
+    #[test]
+    fn account_overrides_leave_unset_fields_at_default() {
+        let address = Address::random();
+        let mut db = CacheDB::new(EmptyDB::default());
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            address,
+            AccountOverride {
+                balance: Some(U256::from(5)),
+                ..Default::default()
+            },
+        );
+
+        RethRunner::<EmptyDB>::apply_account_overrides(&mut db, &overrides).unwrap();
+
+        let info = db.basic(address).unwrap().unwrap();
+        assert_eq!(info.balance, U256::from(5));
+        assert_eq!(info.nonce, 0);
+        assert!(info.code.is_none());
+    }
+
+    #[test]
+    fn block_overrides_patch_only_the_fields_set() {
+        let mut block_env = reth_revm::primitives::BlockEnv::default();
+        block_env.number = U256::from(1);
+        block_env.timestamp = U256::from(100);
+
+        RethRunner::<EmptyDB>::apply_block_overrides(
+            &mut block_env,
+            Some(&BlockOverrides {
+                number: Some(U256::from(99)),
+                timestamp: None,
+                base_fee: None,
+                coinbase: None,
+                gas_limit: None,
+            }),
+        );
+
+        assert_eq!(block_env.number, U256::from(99));
+        assert_eq!(block_env.timestamp, U256::from(100));
+    }
+
+    #[test]
+    fn block_overrides_none_leaves_block_env_untouched() {
+        let mut block_env = reth_revm::primitives::BlockEnv::default();
+        block_env.number = U256::from(1);
+
+        RethRunner::<EmptyDB>::apply_block_overrides(&mut block_env, None);
+
+        assert_eq!(block_env.number, U256::from(1));
+    }
+
+    #[test]
+    fn gas_bounded_prefix_stops_at_the_block_gas_limit() {
+        let outcomes = vec![
+            simulated(legacy_tx(0, 21_000, 1, 0), 21_000, 21_000),
+            simulated(legacy_tx(1, 21_000, 1, 0), 21_000, 42_000),
+            simulated(legacy_tx(2, 21_000, 1, 0), 21_000, 63_000),
+        ];
+
+        let (included, receipts, cumulative_gas_used) =
+            RethRunner::<EmptyDB>::select_gas_bounded_prefix(outcomes, 50_000);
+
+        // Only the first two txs fit; the third would push past the 50k gas limit.
+        assert_eq!(included.len(), 2);
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(cumulative_gas_used, 42_000);
+    }
+
+    #[test]
+    fn execute_if_valid_reuses_the_same_db_so_a_drained_sender_drops_its_next_tx() {
+        let sender = Address::random();
+        let gas_limit = 21_000;
+        let gas_price = 1_000_000_000u128;
+        // Exactly enough balance for one transfer's gas allowance, not two.
+        let balance = U256::from(gas_limit) * U256::from(gas_price);
+        let db = db_with_account(sender, balance, 0);
+
+        let evm_config = EthEvmConfig::default();
+        let env = test_env();
+
+        let tx1 = legacy_tx(0, gas_limit, gas_price, 0);
+        let (db, result1) =
+            RethRunner::<EmptyDB>::execute_if_valid(&evm_config, db, &env, sender, &tx1, None)
+                .unwrap();
+        assert!(
+            result1.is_some(),
+            "first tx should execute and drain the sender's gas allowance"
+        );
+
+        let tx2 = legacy_tx(1, gas_limit, gas_price, 0);
+        let (_db, result2) =
+            RethRunner::<EmptyDB>::execute_if_valid(&evm_config, db, &env, sender, &tx2, None)
+                .unwrap();
+        assert!(
+            result2.is_none(),
+            "second tx must be dropped: it's run against the same CacheDB tx1 committed into"
+        );
+    }
+}